@@ -0,0 +1,296 @@
+use crate::table::{FooterMode, IndexMode, Overflow, TableView};
+use crate::theme::Theme;
+use crate::{OutStream, PipelineElement, ReturnValue, Value};
+use futures::stream;
+use futures::stream::StreamExt;
+
+use crossterm::cursor::MoveTo;
+use crossterm::event::{read, Event, KeyCode};
+use crossterm::style::{Attribute, SetAttribute};
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, size, Clear, ClearType, EnterAlternateScreen,
+    LeaveAlternateScreen,
+};
+use crossterm::{execute, Result as CrosstermResult};
+use std::io::{stdout, Write};
+
+/// A `less`-like alternative to `table` for output that doesn't fit on
+/// screen: rather than paging `STREAM_PAGE_SIZE` rows to stdout, it collects
+/// the whole stream up front and drives a scrollable full-screen view over
+/// it, reusing `TableView::from_list` to render whatever's currently on
+/// screen.
+pub struct ExploreCommand {
+    theme: Theme,
+}
+
+impl ExploreCommand {
+    pub fn new() -> ExploreCommand {
+        ExploreCommand {
+            theme: Theme::default(),
+        }
+    }
+}
+
+impl PipelineElement for ExploreCommand {
+    fn run(self: Box<Self>, input: OutStream<Value>) -> OutStream<ReturnValue> {
+        // `explore` is a sink, same as `table`: it drives a TUI as a side
+        // effect and has no values of its own to hand downstream.
+        Box::new(stream::once(ExploreCommand::explore(input, self.theme)).flat_map(|_| stream::empty()))
+    }
+}
+
+impl ExploreCommand {
+    async fn explore(mut input: OutStream<Value>, theme: Theme) {
+        let mut values = vec![];
+        while let Some(value) = input.next().await {
+            values.push(value);
+        }
+
+        if values.is_empty() {
+            return;
+        }
+
+        if let Err(err) = run_pager(values, theme) {
+            eprintln!("explore: {}", err);
+        }
+    }
+}
+
+/// One level of the drill-down stack: the rows currently on screen, the
+/// selected row (`cursor`, a row index into `values`), and the viewport
+/// (`top_line`, a *line* index into the rendered table, since a wrapped
+/// cell can make one row span more than one screen line).
+struct Frame {
+    values: Vec<Value>,
+    cursor: usize,
+    top_line: usize,
+    left: usize,
+}
+
+impl Frame {
+    fn new(values: Vec<Value>) -> Frame {
+        Frame {
+            values,
+            cursor: 0,
+            top_line: 0,
+            left: 0,
+        }
+    }
+}
+
+/// Drives the alternate-screen pager until the user quits, restoring the
+/// terminal afterwards regardless of how the loop ends.
+fn run_pager(root: Vec<Value>, theme: Theme) -> CrosstermResult<()> {
+    let mut stdout = stdout();
+
+    enable_raw_mode()?;
+    execute!(stdout, EnterAlternateScreen)?;
+
+    let result = pager_loop(&mut stdout, root, theme);
+
+    execute!(stdout, LeaveAlternateScreen)?;
+    disable_raw_mode()?;
+
+    result
+}
+
+fn pager_loop(
+    stdout: &mut std::io::Stdout,
+    root: Vec<Value>,
+    theme: Theme,
+) -> CrosstermResult<()> {
+    let mut stack = vec![Frame::new(root)];
+
+    loop {
+        let (lines, ranges) = {
+            let frame = stack.last().expect("pager stack is never empty");
+            let view = TableView::from_list(
+                &frame.values,
+                0,
+                theme,
+                Overflow::default(),
+                FooterMode::default(),
+                IndexMode::default(),
+            );
+            match view {
+                Some(view) => (view.render_lines(), view.row_line_ranges()),
+                None => (vec![], vec![]),
+            }
+        };
+
+        let height = viewport_height()?;
+
+        {
+            let frame = stack.last().expect("pager stack is never empty");
+            draw_screen(stdout, &lines, &ranges, frame, stack.len())?;
+        }
+
+        match read()? {
+            Event::Key(key) => {
+                let frame = stack.last_mut().expect("pager stack is never empty");
+                match key.code {
+                    KeyCode::Char('q') => break,
+                    KeyCode::Esc => {
+                        if stack.len() > 1 {
+                            stack.pop();
+                        } else {
+                            break;
+                        }
+                    }
+                    KeyCode::Down => move_cursor(frame, &ranges, height, 1),
+                    KeyCode::Up => move_cursor(frame, &ranges, height, -1),
+                    KeyCode::PageDown => page(frame, &ranges, height, 1),
+                    KeyCode::PageUp => page(frame, &ranges, height, -1),
+                    KeyCode::Right => frame.left = frame.left.saturating_add(4),
+                    KeyCode::Left => frame.left = frame.left.saturating_sub(4),
+                    KeyCode::Enter => {
+                        if let Some(nested) = drill_into(frame.values.get(frame.cursor)) {
+                            stack.push(Frame::new(nested));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Moves the selection cursor by `delta` rows, then scrolls `top_line` just
+/// enough to bring the newly selected row's full line range back into
+/// `[top_line, top_line + height)` — keeping the cursor and viewport in
+/// lockstep instead of letting them drift apart.
+fn move_cursor(frame: &mut Frame, ranges: &[(usize, usize)], height: usize, delta: isize) {
+    if ranges.is_empty() {
+        return;
+    }
+
+    frame.cursor = if delta < 0 {
+        frame.cursor.saturating_sub((-delta) as usize)
+    } else {
+        (frame.cursor + delta as usize).min(ranges.len() - 1)
+    };
+
+    scroll_to_cursor(frame, ranges, height);
+}
+
+/// Scrolls a full screen's worth of lines, then snaps the cursor to
+/// whichever row now starts the new page, so paging and selection move
+/// together rather than one leaving the other behind.
+fn page(frame: &mut Frame, ranges: &[(usize, usize)], height: usize, delta: isize) {
+    if ranges.is_empty() {
+        return;
+    }
+
+    let total_lines = ranges.last().map(|r| r.1).unwrap_or(0);
+    let max_top = total_lines.saturating_sub(height);
+
+    frame.top_line = if delta < 0 {
+        frame.top_line.saturating_sub(height)
+    } else {
+        (frame.top_line + height).min(max_top)
+    };
+
+    frame.cursor = row_at_line(ranges, frame.top_line);
+    scroll_to_cursor(frame, ranges, height);
+}
+
+/// Nudges `top_line` just enough that `frame.cursor`'s full line range is
+/// visible, scrolling up or down as needed rather than recentring.
+fn scroll_to_cursor(frame: &mut Frame, ranges: &[(usize, usize)], height: usize) {
+    let (start, end) = ranges[frame.cursor];
+
+    if start < frame.top_line {
+        frame.top_line = start;
+    } else if end > frame.top_line + height {
+        frame.top_line = end.saturating_sub(height);
+    }
+}
+
+/// The row whose line range contains `line`, clamped to the first row if
+/// `line` is above it (the border and header lines) and to the last row if
+/// `line` is past the end (e.g. paging past the final page).
+fn row_at_line(ranges: &[(usize, usize)], line: usize) -> usize {
+    if ranges.first().map_or(false, |&(start, _)| line < start) {
+        return 0;
+    }
+
+    ranges
+        .iter()
+        .position(|&(start, end)| line >= start && line < end)
+        .unwrap_or_else(|| ranges.len().saturating_sub(1))
+}
+
+/// A selected row can be drilled into when it's itself a compound value;
+/// everything else is a leaf and `enter` is a no-op.
+fn drill_into(selected: Option<&Value>) -> Option<Vec<Value>> {
+    match selected {
+        Some(Value::Row(row)) => Some(row.values().cloned().collect()),
+        Some(Value::List(list)) => Some(list.clone()),
+        _ => None,
+    }
+}
+
+fn viewport_height() -> CrosstermResult<usize> {
+    let (_cols, rows) = size()?;
+    Ok(rows.saturating_sub(1) as usize)
+}
+
+fn draw_screen(
+    stdout: &mut std::io::Stdout,
+    lines: &[String],
+    ranges: &[(usize, usize)],
+    frame: &Frame,
+    depth: usize,
+) -> CrosstermResult<()> {
+    let height = viewport_height()?;
+    let (selected_start, selected_end) = ranges
+        .get(frame.cursor)
+        .copied()
+        .unwrap_or((usize::MAX, usize::MAX));
+
+    execute!(stdout, Clear(ClearType::All))?;
+
+    for (screen_row, (line_idx, line)) in lines
+        .iter()
+        .enumerate()
+        .skip(frame.top_line)
+        .take(height)
+        .enumerate()
+    {
+        let visible: String = line.chars().skip(frame.left).collect();
+        execute!(stdout, MoveTo(0, screen_row as u16))?;
+
+        let highlighted = line_idx >= selected_start && line_idx < selected_end;
+        if highlighted {
+            execute!(stdout, SetAttribute(Attribute::Reverse))?;
+        }
+        write!(stdout, "{}", visible)?;
+        if highlighted {
+            execute!(stdout, SetAttribute(Attribute::Reset))?;
+        }
+    }
+
+    // Visible row range, derived from which row line ranges overlap the
+    // current viewport rather than assuming one row is always one line.
+    let first_visible = row_at_line(ranges, frame.top_line);
+    let last_visible = row_at_line(ranges, frame.top_line + height.saturating_sub(1));
+
+    let (_cols, rows) = size()?;
+    execute!(stdout, MoveTo(0, rows.saturating_sub(1)))?;
+    write!(
+        stdout,
+        "rows {}-{}/{}  selected {}  depth {}  (enter: drill in, esc: back/quit, arrows/pgup/pgdn: scroll, q: quit)",
+        first_visible + 1,
+        last_visible + 1,
+        frame.values.len(),
+        frame.cursor + 1,
+        depth,
+    )?;
+
+    stdout.flush()?;
+
+    Ok(())
+}
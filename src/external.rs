@@ -0,0 +1,173 @@
+use crate::{OutStream, PipelineElement, PipelineError, ReturnValue, Value};
+use futures::stream;
+use futures::stream::StreamExt;
+use smol::blocking;
+use std::io::{Read, Write};
+use std::process::{Command, ExitStatus, Stdio};
+use std::time::Duration;
+
+/// Runs an OS process as a pipeline stage. Upstream `Value`s are either
+/// substituted into the argument list wherever a literal `$it` token
+/// appears (one child spawned per row) or, if no `$it` is present, joined
+/// and piped to the child's stdin (a single child for the whole stream).
+/// Either way stdout comes back out as ordinary `Value`s, so builtins and
+/// externals can sit in the same pipeline: `ls | where ... | grep foo`.
+pub struct RunExternalCommand {
+    name: String,
+    args: Vec<String>,
+    ctrl_c: piper::Receiver<()>,
+}
+
+impl RunExternalCommand {
+    pub fn new(name: String, args: Vec<String>, ctrl_c: piper::Receiver<()>) -> RunExternalCommand {
+        RunExternalCommand {
+            name,
+            args,
+            ctrl_c,
+        }
+    }
+}
+
+impl PipelineElement for RunExternalCommand {
+    fn run(self: Box<Self>, input: OutStream<Value>) -> OutStream<ReturnValue> {
+        let has_it = self.args.iter().any(|a| a == "$it");
+        let name = self.name;
+        let args = self.args;
+        let ctrl_c = self.ctrl_c;
+
+        if has_it {
+            let stream = input.flat_map(move |value| {
+                let name = name.clone();
+                let args = substitute_it(&args, &value);
+                let ctrl_c = ctrl_c.clone();
+
+                stream::once(async move { run_external(name, args, None, ctrl_c).await })
+                    .flat_map(stream::iter)
+            });
+
+            Box::new(stream)
+        } else {
+            let stream = stream::once(async move {
+                let stdin = collect_stdin(input).await;
+                run_external(name, args, Some(stdin), ctrl_c).await
+            })
+            .flat_map(stream::iter);
+
+            Box::new(stream)
+        }
+    }
+}
+
+fn substitute_it(args: &[String], value: &Value) -> Vec<String> {
+    let it = format!("{}", value);
+    args.iter()
+        .map(|a| if a == "$it" { it.clone() } else { a.clone() })
+        .collect()
+}
+
+async fn collect_stdin(mut input: OutStream<Value>) -> String {
+    let mut buf = String::new();
+    while let Some(value) = input.next().await {
+        buf.push_str(&format!("{}", value));
+        buf.push('\n');
+    }
+    buf
+}
+
+/// Runs `name args` to completion, racing its exit against `ctrl_c` so an
+/// interrupt kills the child instead of merely erroring the stream out from
+/// under it. Returns the collected stdout as `Value`s, or a single
+/// `ReturnValue::Error` if the process failed to start, was interrupted, or
+/// exited non-zero.
+async fn run_external(
+    name: String,
+    args: Vec<String>,
+    stdin_data: Option<String>,
+    ctrl_c: piper::Receiver<()>,
+) -> Vec<ReturnValue> {
+    let result: std::io::Result<(ExitStatus, String)> = blocking!({
+        let mut child = Command::new(&name)
+            .args(&args)
+            .stdin(if stdin_data.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .spawn()?;
+
+        // Writing stdin and draining stdout happen on their own threads,
+        // concurrently with the wait loop below: a child that fills the
+        // stdout pipe before it's done reading stdin (or vice versa) would
+        // otherwise block a write/read that only happens after `try_wait`
+        // reports exit, and the two sides would deadlock against each
+        // other's full pipe buffer.
+        let stdin_writer = stdin_data.map(|data| {
+            let mut child_stdin = child.stdin.take().expect("stdin was piped");
+            std::thread::spawn(move || {
+                let _ = child_stdin.write_all(data.as_bytes());
+            })
+        });
+
+        let mut child_stdout = child.stdout.take().expect("stdout was piped");
+        let stdout_reader = std::thread::spawn(move || {
+            let mut stdout = String::new();
+            let _ = child_stdout.read_to_string(&mut stdout);
+            stdout
+        });
+
+        let status = loop {
+            if ctrl_c.try_recv().is_some() {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::Interrupted,
+                    format!("{} interrupted by Ctrl-C", name),
+                ));
+            }
+
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+
+            std::thread::sleep(Duration::from_millis(10));
+        };
+
+        if let Some(writer) = stdin_writer {
+            let _ = writer.join();
+        }
+        let stdout = stdout_reader.join().unwrap_or_default();
+
+        Ok((status, stdout))
+    });
+
+    match result {
+        Ok((status, stdout)) if status.success() => {
+            stdout.lines().map(line_to_return_value).collect()
+        }
+        Ok((status, _)) => vec![ReturnValue::Error(error(format!(
+            "{} exited with {}",
+            name, status
+        )))],
+        Err(e) => vec![ReturnValue::Error(error(e.to_string()))],
+    }
+}
+
+fn error(message: String) -> PipelineError {
+    Box::new(std::io::Error::new(std::io::ErrorKind::Other, message))
+}
+
+/// Lines with a run of two or more spaces are treated as simple
+/// whitespace-aligned tables and split into a `Value::Row`; anything else
+/// stays a plain `Value::String`.
+fn line_to_return_value(line: &str) -> ReturnValue {
+    if line.contains("  ") {
+        let mut row = indexmap::IndexMap::new();
+        for (idx, field) in line.split_whitespace().enumerate() {
+            row.insert(format!("col{}", idx), Value::String(field.to_owned()));
+        }
+        ReturnValue::Value(Value::Row(row))
+    } else {
+        ReturnValue::Value(Value::String(line.to_owned()))
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{OutStream, PipelineElement, ReturnValue, Value};
+use futures::stream;
+use futures::stream::StreamExt;
+use indexmap::IndexMap;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub idx: usize,
+    pub cmd: String,
+    pub status: String,
+    pub started: u64,
+    pub duration: Duration,
+}
+
+/// Records executed pipelines (source text, timing, and exit status) to a
+/// file on disk, so later sessions can list past runs via the `history`
+/// command and a `!N` invocation can replay entry `N` through the same
+/// pipeline builder `main` used originally.
+pub struct History {
+    path: PathBuf,
+    entries: Vec<HistoryEntry>,
+}
+
+impl History {
+    pub fn load(path: impl Into<PathBuf>) -> History {
+        let path = path.into();
+
+        let mut entries: Vec<HistoryEntry> = fs::read_to_string(&path)
+            .map(|text| text.lines().filter_map(parse_entry).collect())
+            .unwrap_or_default();
+
+        for (idx, entry) in entries.iter_mut().enumerate() {
+            entry.idx = idx;
+        }
+
+        History { path, entries }
+    }
+
+    /// Appends a completed run to both the in-memory list and the on-disk
+    /// log, returning its index.
+    pub fn record(&mut self, cmd: String, status: &str, duration: Duration) -> usize {
+        let idx = self.entries.len();
+        let started = SystemTime::now()
+            .checked_sub(duration)
+            .unwrap_or(UNIX_EPOCH)
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let entry = HistoryEntry {
+            idx,
+            cmd,
+            status: status.to_owned(),
+            started,
+            duration,
+        };
+
+        self.append_to_disk(&entry);
+        self.entries.push(entry);
+        idx
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&HistoryEntry> {
+        self.entries.get(idx)
+    }
+
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    fn append_to_disk(&self, entry: &HistoryEntry) {
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path);
+
+        if let Ok(mut file) = file {
+            let _ = writeln!(
+                file,
+                "{}\t{}\t{}\t{}",
+                entry.cmd,
+                entry.status,
+                entry.started,
+                entry.duration.as_millis()
+            );
+        }
+    }
+}
+
+fn parse_entry(line: &str) -> Option<HistoryEntry> {
+    let mut fields = line.splitn(4, '\t');
+
+    Some(HistoryEntry {
+        idx: 0, // fixed up by `History::load` once the full list is known
+        cmd: fields.next()?.to_owned(),
+        status: fields.next()?.to_owned(),
+        started: fields.next()?.parse().ok()?,
+        duration: Duration::from_millis(fields.next()?.parse().ok()?),
+    })
+}
+
+/// Streams past history entries as rows (`idx`, `cmd`, `status`,
+/// `duration`), the same way any other source command hands rows
+/// downstream.
+pub struct HistoryCommand {
+    history: Arc<Mutex<History>>,
+}
+
+impl HistoryCommand {
+    pub fn new(history: Arc<Mutex<History>>) -> HistoryCommand {
+        HistoryCommand { history }
+    }
+}
+
+impl PipelineElement for HistoryCommand {
+    fn run(self: Box<Self>, _input: OutStream<Value>) -> OutStream<ReturnValue> {
+        let rows: Vec<Value> = {
+            let history = self.history.lock().expect("history lock poisoned");
+            history
+                .entries()
+                .iter()
+                .map(|entry| {
+                    let mut row = IndexMap::new();
+                    row.insert("idx".to_owned(), Value::String(entry.idx.to_string()));
+                    row.insert("cmd".to_owned(), Value::String(entry.cmd.clone()));
+                    row.insert("status".to_owned(), Value::String(entry.status.clone()));
+                    row.insert(
+                        "duration".to_owned(),
+                        Value::String(format!("{}ms", entry.duration.as_millis())),
+                    );
+                    Value::Row(row)
+                })
+                .collect()
+        };
+
+        Box::new(stream::iter(rows).map(ReturnValue::Value))
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{OutStream, PipelineElement, ReturnValue, Value};
+use futures::stream;
+use futures::stream::StreamExt;
+use indexmap::IndexMap;
+
+fn value_to_json(value: &Value) -> serde_json::Value {
+    match value {
+        Value::String(s) => serde_json::Value::String(s.clone()),
+        Value::Bool(b) => serde_json::Value::Bool(*b),
+        Value::Nothing => serde_json::Value::Null,
+        Value::Row(row) => {
+            let mut map = serde_json::Map::new();
+            for (k, v) in row {
+                map.insert(k.clone(), value_to_json(v));
+            }
+            serde_json::Value::Object(map)
+        }
+        Value::List(list) => serde_json::Value::Array(list.iter().map(value_to_json).collect()),
+    }
+}
+
+fn json_to_value(json: serde_json::Value) -> Value {
+    match json {
+        serde_json::Value::Null => Value::Nothing,
+        serde_json::Value::Bool(b) => Value::Bool(b),
+        // `Value` has no numeric variant, so numbers round-trip as strings.
+        serde_json::Value::Number(n) => Value::String(n.to_string()),
+        serde_json::Value::String(s) => Value::String(s),
+        serde_json::Value::Array(arr) => Value::List(arr.into_iter().map(json_to_value).collect()),
+        serde_json::Value::Object(map) => {
+            let mut row = IndexMap::new();
+            for (k, v) in map {
+                row.insert(k, json_to_value(v));
+            }
+            Value::Row(row)
+        }
+    }
+}
+
+/// Collects the whole upstream stream and emits a single pretty-printed
+/// JSON string: one value becomes its own JSON, more than one becomes a
+/// JSON array, matching how `ls | to json` round-trips a whole directory
+/// listing as one document.
+pub struct ToJsonCommand;
+
+impl ToJsonCommand {
+    pub fn new() -> ToJsonCommand {
+        ToJsonCommand
+    }
+}
+
+impl PipelineElement for ToJsonCommand {
+    fn run(self: Box<Self>, input: OutStream<Value>) -> OutStream<ReturnValue> {
+        let stream = stream::once(async move {
+            let values: Vec<Value> = input.collect().await;
+
+            let json = match values.len() {
+                0 => serde_json::Value::Null,
+                1 => value_to_json(&values[0]),
+                _ => serde_json::Value::Array(values.iter().map(value_to_json).collect()),
+            };
+
+            let rendered = serde_json::to_string_pretty(&json).unwrap_or_default();
+            ReturnValue::Value(Value::String(rendered))
+        });
+
+        Box::new(stream)
+    }
+}
+
+/// Parses each upstream string as JSON back into the `Value` tree. A JSON
+/// array fans out into one row per element (so `open file.json | from json
+/// | where ...` can filter the records), anything else becomes a single
+/// value. Non-string input passes through unchanged; text that doesn't
+/// parse as JSON is dropped.
+pub struct FromJsonCommand;
+
+impl FromJsonCommand {
+    pub fn new() -> FromJsonCommand {
+        FromJsonCommand
+    }
+}
+
+impl PipelineElement for FromJsonCommand {
+    fn run(self: Box<Self>, input: OutStream<Value>) -> OutStream<ReturnValue> {
+        let stream = input
+            .flat_map(|value| {
+                let text = match value {
+                    Value::String(s) => s,
+                    other => return stream::iter(vec![other]),
+                };
+
+                match serde_json::from_str::<serde_json::Value>(&text) {
+                    Ok(serde_json::Value::Array(items)) => {
+                        stream::iter(items.into_iter().map(json_to_value).collect::<Vec<_>>())
+                    }
+                    Ok(json) => stream::iter(vec![json_to_value(json)]),
+                    Err(_) => stream::iter(vec![]),
+                }
+            })
+            .map(ReturnValue::Value);
+
+        Box::new(stream)
+    }
+}
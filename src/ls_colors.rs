@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::env;
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum PathKind {
+    Dir,
+    Symlink,
+    Executable,
+    File,
+}
+
+/// Classifies a path on disk the same way `ls --color` would, so its style
+/// can be looked up in a parsed `LS_COLORS`.
+pub fn classify_path(path: &str) -> PathKind {
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.file_type().is_symlink() => PathKind::Symlink,
+        Ok(meta) if meta.is_dir() => PathKind::Dir,
+        Ok(meta) => {
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                if meta.permissions().mode() & 0o111 != 0 {
+                    return PathKind::Executable;
+                }
+            }
+            PathKind::File
+        }
+        Err(_) => PathKind::File,
+    }
+}
+
+/// `LS_COLORS`, parsed once into filetype (`di`, `ln`, `ex`, ...) and
+/// `*.ext` glob rules mapped to their raw SGR codes (e.g. `"01;34"`).
+pub struct LsColors {
+    types: HashMap<String, String>,
+    extensions: HashMap<String, String>,
+}
+
+impl LsColors {
+    pub fn from_env() -> LsColors {
+        LsColors::parse(&env::var("LS_COLORS").unwrap_or_default())
+    }
+
+    fn parse(raw: &str) -> LsColors {
+        let mut types = HashMap::new();
+        let mut extensions = HashMap::new();
+
+        for entry in raw.split(':') {
+            let mut parts = entry.splitn(2, '=');
+            let key = match parts.next() {
+                Some(k) if !k.is_empty() => k,
+                _ => continue,
+            };
+            let code = match parts.next() {
+                Some(c) if !c.is_empty() => c,
+                _ => continue,
+            };
+
+            if let Some(ext) = key.strip_prefix("*.") {
+                extensions.insert(ext.to_lowercase(), code.to_owned());
+            } else {
+                types.insert(key.to_owned(), code.to_owned());
+            }
+        }
+
+        LsColors { types, extensions }
+    }
+
+    /// The raw SGR code for `path`, classified as `kind`: a filetype match
+    /// (`di`/`ln`/`ex`/`fi`) wins, falling back to a `*.ext` match for
+    /// plain files.
+    pub fn sgr_for(&self, path: &str, kind: PathKind) -> Option<&str> {
+        let key = match kind {
+            PathKind::Dir => "di",
+            PathKind::Symlink => "ln",
+            PathKind::Executable => "ex",
+            PathKind::File => "fi",
+        };
+
+        if let Some(code) = self.types.get(key) {
+            return Some(code);
+        }
+
+        if kind == PathKind::File {
+            let ext = std::path::Path::new(path)
+                .extension()
+                .and_then(|e| e.to_str())
+                .map(|e| e.to_lowercase())?;
+            return self.extensions.get(&ext).map(String::as_str);
+        }
+
+        None
+    }
+}
+
+/// prettytable's `style_spec` reads exactly one character after `F`:
+/// `r/g/y/b/m/c/w/d`, uppercase for the bright variant. This is a different
+/// vocabulary from this crate's `str_to_color` (which spells "blue" `u` and
+/// "black" `b`) — conflating the two silently drops colors (`34` blue would
+/// map to the unrecognized `u`) or swaps them (`30` black would render as
+/// `b`, i.e. blue).
+fn sgr_foreground_letter(code: u32) -> Option<&'static str> {
+    match code {
+        30 => Some("d"),
+        31 => Some("r"),
+        32 => Some("g"),
+        33 => Some("y"),
+        34 => Some("b"),
+        35 => Some("m"),
+        36 => Some("c"),
+        37 => Some("w"),
+        90 => Some("D"),
+        91 => Some("R"),
+        92 => Some("G"),
+        93 => Some("Y"),
+        94 => Some("B"),
+        95 => Some("M"),
+        96 => Some("C"),
+        97 => Some("W"),
+        _ => None,
+    }
+}
+
+/// Translates a raw `;`-separated SGR code (as found in `LS_COLORS`) into
+/// this crate's prettytable style-spec shorthand (`"Fgbr"` and friends),
+/// dropping any SGR attribute (backgrounds, blink, ...) the shorthand has
+/// no equivalent for.
+pub fn sgr_to_style_spec(sgr: &str) -> String {
+    let mut spec = String::new();
+
+    for part in sgr.split(';') {
+        match part.parse::<u32>() {
+            Ok(1) => spec.push('b'),
+            Ok(3) => spec.push('i'),
+            Ok(4) => spec.push('u'),
+            Ok(code) => {
+                if let Some(letter) = sgr_foreground_letter(code) {
+                    spec.push('F');
+                    spec.push_str(letter);
+                }
+            }
+            Err(_) => {}
+        }
+    }
+
+    spec
+}
@@ -1,24 +1,38 @@
 use futures::prelude::*;
+use futures::future;
+use futures::stream;
 use futures::stream::StreamExt;
 use smol::{blocking, iter};
 use std::fmt::Display;
 use std::fs;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
-
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+mod explore;
+mod external;
+mod history;
+mod json;
+mod ls_colors;
+mod pareach;
+mod predicate;
+mod split;
 mod table;
+mod theme;
 
-use indexmap::IndexMap;
+use external::RunExternalCommand;
+use history::{History, HistoryCommand};
+use pareach::ParEach;
+use predicate::WhereCommand;
 
-use async_trait::async_trait;
+use indexmap::IndexMap;
 
 type OutStream<T> = Box<dyn std::marker::Send + std::marker::Unpin + futures::Stream<Item = T>>;
 type LazyGlobStream = OutStream<std::result::Result<std::path::PathBuf, glob::GlobError>>;
-type Connector = Box<dyn PipelineConnector + std::marker::Send>;
 type Element = Box<dyn PipelineElement + std::marker::Send>;
-type PipelineError = Box<dyn std::error::Error>;
+type PipelineError = Box<dyn std::error::Error + Send + Sync>;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Value {
     // Basic values
     String(String),
@@ -87,160 +101,199 @@ enum Action {
 enum ReturnValue {
     Action(Action),
     Value(Value),
+    Error(PipelineError),
 }
 
-#[async_trait]
+/// A single stage of a pipeline. Rather than hand-rolling a `next()` poll
+/// loop, a stage consumes the upstream value stream and hands back its own
+/// output stream; composition, back-pressure, and short-circuiting are all
+/// inherited from the underlying `Stream` machinery instead of being
+/// reimplemented per command.
 trait PipelineElement {
-    async fn connect(&mut self, input: Option<Connector>) -> Result<(), PipelineError>;
-    async fn next(&mut self) -> Result<Option<ReturnValue>, PipelineError>;
-}
-
-#[async_trait]
-trait PipelineConnector {
-    async fn connect(&mut self, input: Option<Element>) -> Result<(), PipelineError>;
-    async fn next(&mut self) -> Result<Option<Value>, PipelineError>;
-}
-
-struct WhereCommand {
-    input: Option<Connector>,
-}
-
-impl WhereCommand {
-    fn new() -> WhereCommand {
-        WhereCommand { input: None }
-    }
+    fn run(self: Box<Self>, input: OutStream<Value>) -> OutStream<ReturnValue>;
 }
 
-#[async_trait]
-impl PipelineElement for WhereCommand {
-    async fn connect(&mut self, input: Option<Connector>) -> Result<(), PipelineError> {
-        self.input = input;
-
-        Ok(())
-    }
-
-    async fn next(&mut self) -> Result<Option<ReturnValue>, PipelineError> {
-        if let Some(input) = &mut self.input {
-            while let Some(inp) = input.next().await? {
-                if let Value::Row(s) = &inp {
-                    if let Some(v) = s.get("name") {
-                        if let Value::String(filename) = v {
-                            if !filename.contains("thirdparty") {
-                                return Ok(Some(ReturnValue::Value(inp)));
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        Ok(None)
-    }
-}
-
-struct LsCommand {
-    inner: Option<LazyGlobStream>,
-}
+struct LsCommand;
 
 impl LsCommand {
     fn new() -> LsCommand {
-        LsCommand { inner: None }
+        LsCommand
     }
 }
 
-#[async_trait]
 impl PipelineElement for LsCommand {
-    async fn connect(&mut self, _input: Option<Connector>) -> Result<(), PipelineError> {
-        let dir = blocking!(glob::glob("**/*"))?;
-        let dir = iter(dir);
-
-        self.inner = Some(Box::new(dir));
-
-        Ok(())
+    fn run(self: Box<Self>, _input: OutStream<Value>) -> OutStream<ReturnValue> {
+        let stream = stream::once(async { blocking!(glob::glob("**/*")) })
+            .flat_map(|dir| match dir {
+                Ok(dir) => Box::new(iter(dir)) as LazyGlobStream,
+                Err(_) => Box::new(stream::empty()) as LazyGlobStream,
+            })
+            .flat_map(|entry| {
+                stream::iter(
+                    entry
+                        .ok()
+                        .map(|path| Value::String(path.to_string_lossy().to_string())),
+                )
+            })
+            .map(ReturnValue::Value);
+
+        Box::new(stream)
     }
+}
 
-    async fn next(&mut self) -> Result<Option<ReturnValue>, PipelineError> {
-        if let Some(inner) = &mut self.inner {
-            if let Some(res) = inner.next().await {
-                let res = res?;
-                let metadata = fs::metadata(&res)?;
+/// Per-row metadata lookup for a path produced by `LsCommand`, meant to be
+/// driven through a `ParEach` stage so slow lookups on one row don't stall
+/// the rest of the listing.
+async fn path_metadata(value: Value) -> Value {
+    let path = match value {
+        Value::String(path) => path,
+        other => return other,
+    };
 
-                let mut output = IndexMap::new();
-                output.insert(
-                    "name".to_owned(),
-                    Value::String(res.to_string_lossy().to_string()),
-                );
+    let metadata = match blocking!(fs::metadata(&path)) {
+        Ok(metadata) => metadata,
+        Err(_) => return Value::Nothing,
+    };
 
-                let filetype = if metadata.is_dir() {
-                    Value::String("Dir".to_owned())
-                } else if metadata.is_file() {
-                    Value::String("File".to_owned())
-                } else {
-                    Value::Nothing
-                };
-                output.insert("type".to_owned(), filetype);
+    let mut output = IndexMap::new();
+    output.insert("name".to_owned(), Value::String(path));
 
-                return Ok(Some(ReturnValue::Value(Value::Row(output))));
-            }
-        }
+    let filetype = if metadata.is_dir() {
+        Value::String("Dir".to_owned())
+    } else if metadata.is_file() {
+        Value::String("File".to_owned())
+    } else {
+        Value::Nothing
+    };
+    output.insert("type".to_owned(), filetype);
 
-        Ok(None)
-    }
+    Value::Row(output)
 }
 
+/// Drains the `Action`s out of a stage's output, performing their side
+/// effects (incrementing the shell counter, greeting), and passes the
+/// remaining `Value`s through to the next stage. Also doubles as the
+/// ctrl-c checkpoint between stages: once ctrl-c fires, `take_while` simply
+/// stops polling upstream rather than threading an `Err` back by hand.
 struct ActionRunner {
-    current_shell: Option<Arc<AtomicUsize>>,
-    ctrl_c: Option<piper::Receiver<()>>,
-    input: Option<Element>,
+    current_shell: Arc<AtomicUsize>,
+    errors: Arc<AtomicUsize>,
+    ctrl_c: piper::Receiver<()>,
 }
 
 impl ActionRunner {
-    pub fn new(current_shell: Arc<AtomicUsize>, ctrl_c: piper::Receiver<()>) -> ActionRunner {
+    pub fn new(
+        current_shell: Arc<AtomicUsize>,
+        errors: Arc<AtomicUsize>,
+        ctrl_c: piper::Receiver<()>,
+    ) -> ActionRunner {
         ActionRunner {
-            current_shell: Some(current_shell),
-            ctrl_c: Some(ctrl_c),
-            input: None,
+            current_shell,
+            errors,
+            ctrl_c,
         }
     }
-}
-
-#[async_trait]
-impl PipelineConnector for ActionRunner {
-    async fn connect(&mut self, input: Option<Element>) -> Result<(), PipelineError> {
-        self.input = input;
 
-        Ok(())
-    }
-
-    async fn next(&mut self) -> Result<Option<Value>, PipelineError> {
-        if let Some(input) = &mut self.input {
-            while let Some(res) = input.next().await? {
-                if let Some(ctrl_c) = &mut self.ctrl_c {
-                    if ctrl_c.try_recv().is_some() {
-                        return Err(Box::new(std::io::Error::new(
-                            std::io::ErrorKind::Interrupted,
-                            "Ctrl-C pressed".to_string(),
-                        )));
-                    }
-                }
-                match res {
-                    ReturnValue::Action(Action::Increment) => {
-                        if let Some(current_shell) = &mut self.current_shell {
+    fn run(&self, input: OutStream<ReturnValue>) -> OutStream<Value> {
+        let ctrl_c = self.ctrl_c.clone();
+        let current_shell = self.current_shell.clone();
+        let errors = self.errors.clone();
+
+        let stream = input
+            .take_while(move |_| future::ready(ctrl_c.try_recv().is_none()))
+            .filter_map(move |res| {
+                let current_shell = current_shell.clone();
+                let errors = errors.clone();
+                async move {
+                    match res {
+                        ReturnValue::Action(Action::Increment) => {
                             current_shell.fetch_add(1, Ordering::Relaxed);
+                            None
+                        }
+                        ReturnValue::Action(Action::Greet) => {
+                            println!("Hello world!");
+                            None
+                        }
+                        ReturnValue::Value(value) => Some(value),
+                        ReturnValue::Error(e) => {
+                            eprintln!("error: {}", e);
+                            errors.fetch_add(1, Ordering::Relaxed);
+                            None
                         }
-                    }
-                    ReturnValue::Action(Action::Greet) => {
-                        println!("Hello world!");
-                    }
-                    ReturnValue::Value(value) => {
-                        return Ok(Some(value));
                     }
                 }
-            }
-        }
+            });
+
+        Box::new(stream)
+    }
+}
 
-        Ok(None)
+const HISTORY_PATH: &str = ".enginen_history";
+const DEFAULT_PIPELINE: &str = "ls | par-each metadata | where name =~ thirdparty && type == Dir | echo $it";
+
+/// Builds and drains the one pipeline this crate knows how to run so far.
+/// `main` and a `!N` re-run both go through this, which is the whole point
+/// of recording `cmd` to history: replaying entry `N` means calling this
+/// again rather than re-parsing anything.
+async fn run_pipeline(
+    cmd: &str,
+    counter: Arc<AtomicUsize>,
+    ctrl_c: piper::Receiver<()>,
+) -> Result<bool, PipelineError> {
+    let errors = Arc::new(AtomicUsize::new(0));
+
+    let ls: Element = Box::new(LsCommand::new());
+    let glue = ActionRunner::new(counter.clone(), errors.clone(), ctrl_c.clone());
+    let ls_values = glue.run(ls.run(Box::new(stream::empty())));
+
+    let metadata: Element = Box::new(ParEach::new(path_metadata, ctrl_c.clone()));
+    let glue = ActionRunner::new(counter.clone(), errors.clone(), ctrl_c.clone());
+    let ls_values = glue.run(metadata.run(ls_values));
+
+    let where_: Element = Box::new(WhereCommand::new("name =~ thirdparty && type == Dir")?);
+    let glue = ActionRunner::new(counter.clone(), errors.clone(), ctrl_c.clone());
+    let where_values = glue.run(where_.run(ls_values));
+
+    let echo: Element = Box::new(RunExternalCommand::new(
+        "echo".to_owned(),
+        vec!["$it".to_owned()],
+        ctrl_c.clone(),
+    ));
+    let drain = ActionRunner::new(counter.clone(), errors.clone(), ctrl_c.clone());
+    let mut values = drain.run(echo.run(where_values));
+
+    while let Some(res) = values.next().await {
+        println!("{}", res);
     }
+
+    let _ = cmd;
+    Ok(errors.load(Ordering::Relaxed) == 0)
+}
+
+/// Runs `cmd` through `run_pipeline`, timing it and recording the outcome
+/// to `history`.
+async fn run_and_record(
+    history: &Arc<Mutex<History>>,
+    cmd: &str,
+    counter: Arc<AtomicUsize>,
+    ctrl_c: piper::Receiver<()>,
+) -> Result<(), PipelineError> {
+    let start = Instant::now();
+    let result = run_pipeline(cmd, counter, ctrl_c).await;
+    let duration = start.elapsed();
+
+    let status = match &result {
+        Ok(true) => "ok",
+        Ok(false) => "error",
+        Err(_) => "error",
+    };
+
+    history
+        .lock()
+        .expect("history lock poisoned")
+        .record(cmd.to_owned(), status, duration);
+
+    result.map(|_| ())
 }
 
 fn main() -> Result<(), PipelineError> {
@@ -251,26 +304,40 @@ fn main() -> Result<(), PipelineError> {
     ctrlc::set_handler(handle).unwrap();
 
     let counter = Arc::new(AtomicUsize::new(10));
+    let history = Arc::new(Mutex::new(History::load(HISTORY_PATH)));
 
-    smol::run(async {
-        // Build up our pipeline: ls | where name =~ thirdparty
-        let mut ls = LsCommand::new();
-        ls.connect(None).await?;
-
-        let mut glue = ActionRunner::new(counter.clone(), ctrl_c.clone());
-        glue.connect(Some(Box::new(ls))).await?;
-
-        let mut where_ = WhereCommand::new();
-        where_.connect(Some(Box::new(glue))).await?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
 
-        let mut drain = ActionRunner::new(counter.clone(), ctrl_c.clone());
-        drain.connect(Some(Box::new(where_))).await?;
+    smol::run(async {
+        match args.get(0).map(String::as_str) {
+            Some("history") => {
+                let rows: Element = Box::new(HistoryCommand::new(history.clone()));
+                let drain = ActionRunner::new(
+                    counter.clone(),
+                    Arc::new(AtomicUsize::new(0)),
+                    ctrl_c.clone(),
+                );
+                let mut rows = drain.run(rows.run(Box::new(stream::empty())));
 
-        while let Some(res) = drain.next().await? {
-            // if ctrl_c.try_recv().is_some() {
-            //     break;
-            // }
-            println!("{}", res);
+                while let Some(res) = rows.next().await {
+                    println!("{}", res);
+                }
+            }
+            Some(arg) if arg.starts_with('!') => {
+                let idx: Option<usize> = arg[1..].parse().ok();
+                let cmd = idx.and_then(|idx| history.lock().expect("history lock poisoned").get(idx).map(|e| e.cmd.clone()));
+
+                match cmd {
+                    Some(cmd) => {
+                        println!("re-running #{}: {}", idx.unwrap(), cmd);
+                        run_and_record(&history, &cmd, counter.clone(), ctrl_c.clone()).await?;
+                    }
+                    None => eprintln!("no history entry {}", arg),
+                }
+            }
+            _ => {
+                run_and_record(&history, DEFAULT_PIPELINE, counter.clone(), ctrl_c.clone()).await?;
+            }
         }
 
         dbg!(counter);
@@ -0,0 +1,65 @@
+use crate::{OutStream, PipelineElement, ReturnValue, Value};
+use futures::future::{self, Either};
+use futures::stream::StreamExt;
+use std::future::Future;
+use std::sync::Arc;
+
+/// Runs a per-row operation over up to `limit` rows concurrently instead of
+/// strictly one-at-a-time, so a slow row (an I/O-heavy lookup, say) doesn't
+/// stall the rest of the stream. Each buffered future is raced against
+/// `ctrl_c` so an interrupt drops it rather than waiting it out.
+pub struct ParEach<F> {
+    limit: usize,
+    ctrl_c: piper::Receiver<()>,
+    op: Arc<F>,
+}
+
+impl<F, Fut> ParEach<F>
+where
+    F: Fn(Value) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Value> + Send + 'static,
+{
+    /// Concurrency defaults to the CPU count.
+    pub fn new(op: F, ctrl_c: piper::Receiver<()>) -> ParEach<F> {
+        ParEach::with_limit(op, ctrl_c, num_cpus::get())
+    }
+
+    pub fn with_limit(op: F, ctrl_c: piper::Receiver<()>, limit: usize) -> ParEach<F> {
+        ParEach {
+            limit,
+            ctrl_c,
+            op: Arc::new(op),
+        }
+    }
+}
+
+impl<F, Fut> PipelineElement for ParEach<F>
+where
+    F: Fn(Value) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Value> + Send + 'static,
+{
+    fn run(self: Box<Self>, input: OutStream<Value>) -> OutStream<ReturnValue> {
+        let op = self.op;
+        let ctrl_c = self.ctrl_c;
+        let limit = self.limit;
+
+        let stream = input
+            .map(move |value| {
+                let op = op.clone();
+                let ctrl_c = ctrl_c.clone();
+
+                async move {
+                    match future::select(Box::pin(op(value)), Box::pin(ctrl_c.into_future())).await
+                    {
+                        Either::Left((value, _)) => Some(value),
+                        Either::Right(_) => None,
+                    }
+                }
+            })
+            .buffer_unordered(limit)
+            .filter_map(future::ready)
+            .map(ReturnValue::Value);
+
+        Box::new(stream)
+    }
+}
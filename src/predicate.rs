@@ -0,0 +1,163 @@
+use crate::{OutStream, PipelineElement, PipelineError, ReturnValue, Value};
+use futures::future;
+use futures::stream::StreamExt;
+use regex::Regex;
+use std::fmt;
+
+#[derive(Debug)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Gt,
+    Match(Regex),
+}
+
+#[derive(Debug)]
+enum Expr {
+    Compare { column: String, op: Op, literal: String },
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug)]
+pub struct PredicateError(String);
+
+impl fmt::Display for PredicateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid predicate: {}", self.0)
+    }
+}
+
+impl std::error::Error for PredicateError {}
+
+/// Parses `name =~ thirdparty && type == Dir` into an `Expr` tree.
+/// Comparisons (`==`, `!=`, `<`, `>`, `=~` regex match) are joined
+/// left-to-right by `&&` (binds tighter) and `||`; each comparison is
+/// `column op literal`. The `=~` pattern is compiled once here, so a bad
+/// regex is reported at parse time rather than on the first row.
+fn parse(input: &str) -> Result<Expr, PredicateError> {
+    input
+        .split("||")
+        .map(parse_and)
+        .try_fold(None, |acc, and_expr| {
+            let and_expr = and_expr?;
+            Ok(Some(match acc {
+                None => and_expr,
+                Some(acc) => Expr::Or(Box::new(acc), Box::new(and_expr)),
+            }))
+        })?
+        .ok_or_else(|| PredicateError("empty predicate".to_owned()))
+}
+
+fn parse_and(input: &str) -> Result<Expr, PredicateError> {
+    input
+        .split("&&")
+        .map(parse_comparison)
+        .try_fold(None, |acc, cmp| {
+            let cmp = cmp?;
+            Ok(Some(match acc {
+                None => cmp,
+                Some(acc) => Expr::And(Box::new(acc), Box::new(cmp)),
+            }))
+        })?
+        .ok_or_else(|| PredicateError("empty predicate".to_owned()))
+}
+
+fn parse_comparison(input: &str) -> Result<Expr, PredicateError> {
+    let tokens: Vec<&str> = input.split_whitespace().collect();
+    if tokens.len() != 3 {
+        return Err(PredicateError(format!(
+            "expected `column op value`, got `{}`",
+            input.trim()
+        )));
+    }
+
+    let op = match tokens[1] {
+        "==" => Op::Eq,
+        "!=" => Op::Ne,
+        "<" => Op::Lt,
+        ">" => Op::Gt,
+        "=~" => Op::Match(Regex::new(tokens[2]).map_err(|e| {
+            PredicateError(format!("invalid regex `{}`: {}", tokens[2], e))
+        })?),
+        other => return Err(PredicateError(format!("unknown operator `{}`", other))),
+    };
+
+    Ok(Expr::Compare {
+        column: tokens[0].to_owned(),
+        op,
+        literal: tokens[2].to_owned(),
+    })
+}
+
+/// Coerces `literal` to compare against `left`'s variant and reports
+/// whether they're equal.
+fn values_eq(left: &Value, literal: &str) -> bool {
+    match left {
+        Value::String(s) => s == literal,
+        Value::Bool(b) => *b == literal.eq_ignore_ascii_case("true"),
+        Value::Nothing => literal.is_empty() || literal.eq_ignore_ascii_case("nothing"),
+        Value::Row(_) | Value::List(_) => false,
+    }
+}
+
+fn compare(op: &Op, left: &Value, literal: &str) -> bool {
+    match op {
+        Op::Eq => values_eq(left, literal),
+        Op::Ne => !values_eq(left, literal),
+        Op::Match(re) => match left {
+            Value::String(s) => re.is_match(s),
+            _ => false,
+        },
+        Op::Lt => match left {
+            Value::String(s) => s.as_str() < literal,
+            _ => false,
+        },
+        Op::Gt => match left {
+            Value::String(s) => s.as_str() > literal,
+            _ => false,
+        },
+    }
+}
+
+fn eval(expr: &Expr, value: &Value) -> bool {
+    match expr {
+        Expr::Compare { column, op, literal } => match value {
+            Value::Row(row) => match row.get(column) {
+                Some(left) => compare(op, left, literal),
+                None => false,
+            },
+            _ => false,
+        },
+        Expr::And(lhs, rhs) => eval(lhs, value) && eval(rhs, value),
+        Expr::Or(lhs, rhs) => eval(lhs, value) || eval(rhs, value),
+    }
+}
+
+/// Filters rows against a compiled boolean-expression predicate instead of
+/// a hardcoded column check, e.g. `name =~ thirdparty && type == Dir`. The
+/// predicate is parsed once in `new` and evaluated per row in `run`.
+pub struct WhereCommand {
+    predicate: Expr,
+}
+
+impl WhereCommand {
+    pub fn new(predicate: &str) -> Result<WhereCommand, PipelineError> {
+        Ok(WhereCommand {
+            predicate: parse(predicate)?,
+        })
+    }
+}
+
+impl PipelineElement for WhereCommand {
+    fn run(self: Box<Self>, input: OutStream<Value>) -> OutStream<ReturnValue> {
+        let predicate = self.predicate;
+
+        let stream = input
+            .filter(move |value| future::ready(eval(&predicate, value)))
+            .map(ReturnValue::Value);
+
+        Box::new(stream)
+    }
+}
@@ -0,0 +1,51 @@
+use crate::{OutStream, PipelineElement, ReturnValue, Value};
+use futures::stream;
+use futures::stream::StreamExt;
+
+/// Expands a single row into several by splitting the string in `column` on
+/// `separator`, emitting one row per fragment with that column replaced.
+/// Rows that aren't a `Value::Row`, or don't have `column` as a string,
+/// pass through unchanged. This is the pipeline's one-to-many case: a
+/// single upstream `Value` turning into a variable number of downstream
+/// `Value`s falls straight out of `flat_map` rather than needing any
+/// buffering of its own.
+pub struct SplitRowCommand {
+    column: String,
+    separator: String,
+}
+
+impl SplitRowCommand {
+    pub fn new(column: String, separator: String) -> SplitRowCommand {
+        SplitRowCommand { column, separator }
+    }
+}
+
+impl PipelineElement for SplitRowCommand {
+    fn run(self: Box<Self>, input: OutStream<Value>) -> OutStream<ReturnValue> {
+        let column = self.column;
+        let separator = self.separator;
+
+        let stream = input
+            .flat_map(move |value| {
+                let rows = match &value {
+                    Value::Row(row) => match row.get(&column) {
+                        Some(Value::String(s)) => s
+                            .split(separator.as_str())
+                            .map(|fragment| {
+                                let mut new_row = row.clone();
+                                new_row.insert(column.clone(), Value::String(fragment.to_owned()));
+                                Value::Row(new_row)
+                            })
+                            .collect(),
+                        _ => vec![value.clone()],
+                    },
+                    _ => vec![value.clone()],
+                };
+
+                stream::iter(rows)
+            })
+            .map(ReturnValue::Value);
+
+        Box::new(stream)
+    }
+}
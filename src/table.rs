@@ -1,30 +1,122 @@
-use crate::{Connector, PipelineElement, PipelineError, ReturnValue, Value};
-use derive_new::new;
+use crate::ls_colors::{classify_path, sgr_to_style_spec, LsColors};
+use crate::theme::Theme;
+use crate::{OutStream, PipelineElement, PipelineError, ReturnValue, Value};
 use textwrap::fill;
 
-use prettytable::format::{Alignment, FormatBuilder, LinePosition, LineSeparator};
+use futures::stream;
+use futures::stream::StreamExt;
+use prettytable::format::Alignment;
 use prettytable::{color, Attr, Cell, Row, Table};
+use rayon::prelude::*;
+
+type Entries = Vec<Vec<(String, String)>>;
+
+/// How an over-wide cell gives up its extra width: `Wrap` (the long-standing
+/// default, via `textwrap::fill`) grows the row instead, while the two
+/// truncate modes keep every row single-line at the cost of the tail of the
+/// text, `TruncateKeepWords` preferring to cut on a word boundary.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    Wrap,
+    Truncate,
+    TruncateKeepWords,
+}
+
+impl Overflow {
+    pub fn from_name(name: &str) -> Overflow {
+        match name {
+            "truncate" => Overflow::Truncate,
+            "truncate-keep-words" => Overflow::TruncateKeepWords,
+            _ => Overflow::Wrap,
+        }
+    }
+}
 
-type Entries = Vec<Vec<(String, &'static str)>>;
+impl Default for Overflow {
+    fn default() -> Overflow {
+        Overflow::Wrap
+    }
+}
 
-use async_trait::async_trait;
+/// Whether the header row is repeated as a footer once a table scrolls past
+/// a single screen, so column names stay visible: `Auto` repeats only past
+/// `FOOTER_AUTO_THRESHOLD` rows, `Always`/`Never` force the choice.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum FooterMode {
+    Auto,
+    Always,
+    Never,
+}
+
+impl FooterMode {
+    pub fn from_name(name: &str) -> FooterMode {
+        match name {
+            "always" => FooterMode::Always,
+            "never" => FooterMode::Never,
+            _ => FooterMode::Auto,
+        }
+    }
+}
+
+impl Default for FooterMode {
+    fn default() -> FooterMode {
+        FooterMode::Auto
+    }
+}
+
+const FOOTER_AUTO_THRESHOLD: usize = 25;
+
+/// Controls the synthesized 0-based index column `values_to_entries`
+/// prepends to every row: `Never` suppresses it, `Always` synthesizes it
+/// unconditionally, and `Auto` (the default) reuses an existing column
+/// literally named `index` instead of duplicating it, falling back to
+/// synthesizing one when no such column exists.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum IndexMode {
+    Auto,
+    Always,
+    Never,
+}
+
+impl IndexMode {
+    pub fn from_name(name: &str) -> IndexMode {
+        match name {
+            "always" => IndexMode::Always,
+            "never" => IndexMode::Never,
+            _ => IndexMode::Auto,
+        }
+    }
+}
+
+impl Default for IndexMode {
+    fn default() -> IndexMode {
+        IndexMode::Auto
+    }
+}
 
-#[derive(Debug, new)]
 pub struct TableView {
     // List of header cell values:
     headers: Vec<String>,
 
     // List of rows of cells, each containing value and prettytable style-string:
     entries: Entries,
-}
 
-enum TableMode {
-    Light,
-    Normal,
+    // Border/separator glyphs to render with:
+    theme: Theme,
+
+    // Whether the header row is repeated at the bottom:
+    footer_mode: FooterMode,
 }
 
 impl TableView {
-    pub fn from_list(values: &[Value], starting_idx: usize) -> Option<TableView> {
+    pub fn from_list(
+        values: &[Value],
+        starting_idx: usize,
+        theme: Theme,
+        overflow: Overflow,
+        footer_mode: FooterMode,
+        index_mode: IndexMode,
+    ) -> Option<TableView> {
         if values.is_empty() {
             return None;
         }
@@ -33,8 +125,12 @@ impl TableView {
         let termwidth = std::cmp::max(textwrap::termwidth() - 7, 20);
         //let termwidth = 76;
 
+        // Parsed once per page rather than per cell.
+        let ls_colors = LsColors::from_env();
+
         let mut headers = merge_descriptors(values);
-        let mut entries = values_to_entries(values, &mut headers, starting_idx);
+        let mut entries =
+            values_to_entries(values, &mut headers, starting_idx, &ls_colors, index_mode);
         let max_per_column = max_per_column(&headers, &entries, values.len());
 
         maybe_truncate_columns(&mut headers, &mut entries, termwidth);
@@ -67,6 +163,9 @@ impl TableView {
             max_per_column,
             max_naive_column_width,
             max_column_width,
+            theme,
+            overflow,
+            footer_mode,
         );
         Some(table_view)
     }
@@ -93,66 +192,145 @@ pub fn merge_descriptors(values: &[Value]) -> Vec<String> {
     ret
 }
 
-fn values_to_entries(values: &[Value], headers: &mut Vec<String>, starting_idx: usize) -> Entries {
-    let mut entries = vec![];
+/// Picks a prettytable style-spec for a leaf `Value`, the same
+/// foreground-color-plus-alignment shorthand the index column already uses
+/// (`"Fgbr"` below). `Value` has no numeric variant of its own, so a string
+/// that parses as a number is treated as one: cyan and right-aligned.
+/// Booleans are yellow, null/empty cells are a dim foreground color (there's
+/// no dim attribute in `style_spec`), everything else is left as the
+/// table's default style. Colors are resolved through `str_to_color` rather
+/// than spelling out spec letters here, so there's one color vocabulary.
+fn style_leaf(value: &Value) -> String {
+    match value {
+        Value::Bool(_) => color_spec("yellow", ""),
+        Value::Nothing => color_spec("black", ""),
+        Value::String(s) if s.is_empty() => color_spec("black", ""),
+        Value::String(s) if s.parse::<f64>().is_ok() => color_spec("cyan", "r"),
+        _ => String::new(),
+    }
+}
+
+/// Looks `color_name` up via `str_to_color` and renders it (plus any
+/// alignment/attribute `suffix`) as a `style_spec` foreground directive.
+fn color_spec(color_name: &str, suffix: &str) -> String {
+    match str_to_color(color_name.to_owned()) {
+        Some(color) => format!("F{}{}", color_to_spec_letter(color), suffix),
+        None => String::new(),
+    }
+}
+
+/// A column literally named `name` is this crate's convention for "this
+/// holds a path" (see `path_metadata`); style it from `LS_COLORS` when the
+/// value matches a file on disk, otherwise fall back to `style_leaf`.
+fn style_cell(column: &str, value: &Value, ls_colors: &LsColors) -> String {
+    if column == "name" {
+        if let Value::String(path) = value {
+            let kind = classify_path(path);
+            if let Some(sgr) = ls_colors.sgr_for(path, kind) {
+                return sgr_to_style_spec(sgr);
+            }
+        }
+    }
+
+    style_leaf(value)
+}
+
+/// Builds each row's cells in parallel over `values` rather than a serial
+/// loop, since per-row formatting/styling is independent work; `par_iter`
+/// is index-preserving, so collecting straight into a `Vec` keeps rows in
+/// their original order without any extra bookkeeping.
+fn values_to_entries(
+    values: &[Value],
+    headers: &mut Vec<String>,
+    starting_idx: usize,
+    ls_colors: &LsColors,
+    index_mode: IndexMode,
+) -> Entries {
+    // An existing `index` column is reused instead of duplicated rather
+    // than synthesizing a running count alongside it; pull it out of the
+    // normal header set so it isn't rendered twice.
+    let reuse_index = index_mode == IndexMode::Auto && headers.iter().any(|h| h == "index");
+    if reuse_index {
+        headers.retain(|h| h != "index");
+    }
 
     if headers.is_empty() {
         headers.push("".to_string());
     }
 
-    for (idx, value) in values.iter().enumerate() {
-        let mut row: Vec<(String, &'static str)> = headers
-            .iter()
-            .map(|d: &String| {
-                if d == "" {
-                    match value {
-                        Value::Row(..) => (String::new(), ""),
-                        _ => (format!("{}", value), ""),
-                    }
-                } else {
-                    match value {
-                        Value::Row(row) => {
-                            let data = row.get(d);
-                            if let Some(data) = data {
-                                (format!("{}", data), "")
-                            } else {
-                                (String::new(), "")
+    values
+        .par_iter()
+        .enumerate()
+        .map(|(idx, value)| {
+            let mut row: Vec<(String, String)> = headers
+                .iter()
+                .map(|d: &String| {
+                    if d == "" {
+                        match value {
+                            Value::Row(..) => (String::new(), String::new()),
+                            _ => (format!("{}", value), style_cell(d, value, ls_colors)),
+                        }
+                    } else {
+                        match value {
+                            Value::Row(row) => {
+                                let data = row.get(d);
+                                if let Some(data) = data {
+                                    (format!("{}", data), style_cell(d, data, ls_colors))
+                                } else {
+                                    (String::new(), String::new())
+                                }
                             }
+                            _ => (format!("{}", value), style_cell(d, value, ls_colors)),
                         }
-                        _ => (format!("{}", value), ""),
                     }
-                }
-            })
-            .collect();
+                })
+                .collect();
 
-        // Indices are green, bold, right-aligned:
-        row.insert(0, ((starting_idx + idx).to_string(), "Fgbr"));
+            if index_mode != IndexMode::Never {
+                let index_cell = if reuse_index {
+                    match value {
+                        Value::Row(row) => row.get("index").map(|v| format!("{}", v)),
+                        _ => None,
+                    }
+                } else {
+                    None
+                }
+                .unwrap_or_else(|| (starting_idx + idx).to_string());
 
-        entries.push(row);
-    }
+                // Indices are green, bold, right-aligned:
+                row.insert(0, (index_cell, "Fgbr".to_owned()));
+            }
 
-    entries
+            row
+        })
+        .collect()
 }
 
+/// Per-column maxima via a parallel fold-reduce instead of a nested serial
+/// loop: each row contributes its own column-width `Vec`, and partial
+/// `Vec`s are folded together element-wise with `max` rather than walking
+/// `entries` column-major.
 #[allow(clippy::ptr_arg)]
 fn max_per_column(headers: &[String], entries: &Entries, values_len: usize) -> Vec<usize> {
-    let mut max_per_column = vec![];
-
-    for i in 0..headers.len() {
-        let mut current_col_max = 0;
-        let iter = entries.iter().take(values_len);
-
-        for entry in iter {
-            let value_length = entry[i].0.chars().count();
-            if value_length > current_col_max {
-                current_col_max = value_length;
-            }
-        }
-
-        max_per_column.push(std::cmp::max(current_col_max, headers[i].chars().count()));
-    }
+    let header_widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
 
-    max_per_column
+    entries
+        .par_iter()
+        .take(values_len)
+        .map(|entry| {
+            (0..headers.len())
+                .map(|i| entry[i].0.chars().count())
+                .collect::<Vec<usize>>()
+        })
+        .reduce(
+            || header_widths.clone(),
+            |mut acc, row| {
+                for (a, r) in acc.iter_mut().zip(row.iter()) {
+                    *a = (*a).max(*r);
+                }
+                acc
+            },
+        )
 }
 
 fn maybe_truncate_columns(headers: &mut Vec<String>, entries: &mut Entries, termwidth: usize) {
@@ -170,7 +348,7 @@ fn maybe_truncate_columns(headers: &mut Vec<String>, entries: &mut Entries, term
         headers.push("...".to_owned());
 
         for entry in entries.iter_mut() {
-            entry.push(("...".to_owned(), "c")); // ellipsis is centred
+            entry.push(("...".to_owned(), "c".to_owned())); // ellipsis is centred
         }
     }
 }
@@ -285,24 +463,65 @@ fn wrap_cells(
     max_per_column: Vec<usize>,
     max_naive_column_width: usize,
     max_column_width: usize,
+    theme: Theme,
+    overflow: Overflow,
+    footer_mode: FooterMode,
 ) -> TableView {
     for head in 0..headers.len() {
         if max_per_column[head] > max_naive_column_width {
-            headers[head] = fill(&headers[head], max_column_width);
+            headers[head] = reflow(&headers[head], max_column_width, overflow);
 
             for entry in entries.iter_mut() {
-                entry[head].0 = fill(&entry[head].0, max_column_width);
+                entry[head].0 = reflow(&entry[head].0, max_column_width, overflow);
             }
         }
     }
 
-    TableView { headers, entries }
+    TableView {
+        headers,
+        entries,
+        theme,
+        footer_mode,
+    }
+}
+
+fn reflow(text: &str, max_column_width: usize, overflow: Overflow) -> String {
+    match overflow {
+        Overflow::Wrap => fill(text, max_column_width),
+        Overflow::Truncate => truncate_ellipsis(text, max_column_width, false),
+        Overflow::TruncateKeepWords => truncate_ellipsis(text, max_column_width, true),
+    }
+}
+
+/// Cuts `text` to `max_column_width` `chars()` (never bytes, so multibyte
+/// content isn't split mid-codepoint), reserving one character for the
+/// trailing `…`. `keep_words` backs the cut point up to the last whitespace
+/// boundary that still fits, instead of cutting mid-word.
+fn truncate_ellipsis(text: &str, max_column_width: usize, keep_words: bool) -> String {
+    if text.chars().count() <= max_column_width {
+        return text.to_owned();
+    }
+
+    if max_column_width == 0 {
+        return String::new();
+    }
+
+    let budget = max_column_width - 1;
+    let mut truncated: String = text.chars().take(budget).collect();
+
+    if keep_words {
+        if let Some(last_space) = truncated.rfind(char::is_whitespace) {
+            truncated.truncate(last_space);
+        }
+    }
+
+    format!("{}…", truncated.trim_end())
 }
 
 impl TableView {
-    fn print_view(&self) -> Result<(), PipelineError> {
+    fn build_table(&self) -> Option<Table> {
         if self.entries.is_empty() {
-            return Ok(());
+            return None;
         }
 
         let mut table = Table::new();
@@ -313,30 +532,7 @@ impl TableView {
 
         let header_style = vec![Attr::Bold];
 
-        let table_mode = TableMode::Normal;
-
-        match table_mode {
-            TableMode::Light => {
-                table.set_format(
-                    FormatBuilder::new()
-                        .separator(LinePosition::Title, LineSeparator::new('─', '─', ' ', ' '))
-                        .separator(LinePosition::Bottom, LineSeparator::new(' ', ' ', ' ', ' '))
-                        .padding(1, 1)
-                        .build(),
-                );
-            }
-            _ => {
-                table.set_format(
-                    FormatBuilder::new()
-                        .column_separator('│')
-                        .separator(LinePosition::Top, LineSeparator::new('─', '┬', ' ', ' '))
-                        .separator(LinePosition::Title, LineSeparator::new('─', '┼', ' ', ' '))
-                        .separator(LinePosition::Bottom, LineSeparator::new('─', '┴', ' ', ' '))
-                        .padding(1, 1)
-                        .build(),
-                );
-            }
-        }
+        table.set_format(self.theme.build_format().build());
 
         let skip_headers = (self.headers.len() == 2 && self.headers[1] == "")
             || (self.headers.len() == 1 && self.headers[0] == "");
@@ -354,6 +550,12 @@ impl TableView {
             })
             .collect();
 
+        let show_footer = match self.footer_mode {
+            FooterMode::Always => true,
+            FooterMode::Never => false,
+            FooterMode::Auto => self.entries.len() > FOOTER_AUTO_THRESHOLD,
+        };
+
         if !skip_headers {
             table.set_titles(Row::new(header));
         }
@@ -361,23 +563,88 @@ impl TableView {
         for row in &self.entries {
             table.add_row(Row::new(
                 row.iter()
-                    .map(|v| Cell::new(&v.0).style_spec(v.1))
+                    .map(|v| Cell::new(&v.0).style_spec(&v.1))
                     .collect(),
             ));
         }
 
-        // for row in &table {
-        //     for cell in row.iter() {
-        //         print!("{} | ", cell.get_content());
-        //     }
-        //     println!("");
-        // }
-        // table.print_term(&mut *host.out_terminal().ok_or_else(|| ShellError::untagged_runtime_error("Could not open terminal for output"))?)
-        //     .map_err(|_| ShellError::untagged_runtime_error("Internal error: could not print to terminal (for unix systems check to make sure TERM is set)"))?;
-        table.printstd();
+        if !skip_headers && show_footer {
+            let footer: Vec<Cell> = self
+                .headers
+                .iter()
+                .map(|h| {
+                    let mut c = Cell::new_align(h, header_align)
+                        .with_style(Attr::ForegroundColor(header_color));
+                    for &s in &header_style {
+                        c.style(s);
+                    }
+                    c
+                })
+                .collect();
+            table.add_row(Row::new(footer));
+        }
+
+        Some(table)
+    }
+
+    fn print_view(&self) -> Result<(), PipelineError> {
+        if let Some(table) = self.build_table() {
+            table.printstd();
+        }
 
         Ok(())
     }
+
+    /// Renders the same grid `print_view` would print to stdout as plain
+    /// lines instead, for callers (the `explore` pager) that need to draw
+    /// into an arbitrary viewport rather than the real terminal cursor.
+    pub fn render_lines(&self) -> Vec<String> {
+        match self.build_table() {
+            Some(table) => table.to_string().lines().map(str::to_owned).collect(),
+            None => vec![],
+        }
+    }
+
+    /// The `[start, end)` line range each data row occupies within
+    /// `render_lines()`, in the same order as `self.entries`, accounting for
+    /// the top border, the header block, and any wrapped cell that spans
+    /// more than one line. Lets a caller (the `explore` pager) map a row
+    /// index to exactly the lines it needs to scroll to or highlight,
+    /// instead of assuming one row is always one line.
+    pub fn row_line_ranges(&self) -> Vec<(usize, usize)> {
+        if self.entries.is_empty() {
+            return vec![];
+        }
+
+        let skip_headers = (self.headers.len() == 2 && self.headers[1] == "")
+            || (self.headers.len() == 1 && self.headers[0] == "");
+
+        let mut offset = 1; // top border
+        if !skip_headers {
+            offset += cell_line_height(&self.headers) + 1; // header block + title separator
+        }
+
+        let mut ranges = Vec::with_capacity(self.entries.len());
+        for row in &self.entries {
+            let height = row
+                .iter()
+                .map(|cell| line_height(&cell.0))
+                .max()
+                .unwrap_or(1);
+            ranges.push((offset, offset + height));
+            offset += height;
+        }
+
+        ranges
+    }
+}
+
+fn line_height(text: &str) -> usize {
+    text.matches('\n').count() + 1
+}
+
+fn cell_line_height(cells: &[String]) -> usize {
+    cells.iter().map(|c| line_height(c)).max().unwrap_or(1)
 }
 
 fn str_to_color(s: String) -> Option<color::Color> {
@@ -401,6 +668,30 @@ fn str_to_color(s: String) -> Option<color::Color> {
     }
 }
 
+/// The `style_spec` foreground letter for a `color::Color` (`r/g/y/b/m/c/w/d`,
+/// uppercase for the bright variant) — the counterpart `style_leaf` needs to
+/// turn a `str_to_color` lookup back into a spec string.
+fn color_to_spec_letter(color: color::Color) -> char {
+    match color {
+        color::RED => 'r',
+        color::GREEN => 'g',
+        color::YELLOW => 'y',
+        color::BLUE => 'b',
+        color::MAGENTA => 'm',
+        color::CYAN => 'c',
+        color::WHITE => 'w',
+        color::BLACK => 'd',
+        color::BRIGHT_RED => 'R',
+        color::BRIGHT_GREEN => 'G',
+        color::BRIGHT_YELLOW => 'Y',
+        color::BRIGHT_BLUE => 'B',
+        color::BRIGHT_MAGENTA => 'M',
+        color::BRIGHT_CYAN => 'C',
+        color::BRIGHT_WHITE => 'W',
+        _ => 'w',
+    }
+}
+
 // fn to_style_vec(a: Vec<Value>) -> Vec<Attr> {
 //     let mut v: Vec<Attr> = Vec::new();
 //     for t in a {
@@ -423,31 +714,66 @@ fn str_to_color(s: String) -> Option<color::Color> {
 // }
 
 pub struct TableCommand {
-    input: Option<Connector>,
+    theme: Theme,
+    overflow: Overflow,
+    footer_mode: FooterMode,
+    index_mode: IndexMode,
 }
 
 impl TableCommand {
     pub fn new() -> TableCommand {
-        TableCommand { input: None }
+        TableCommand {
+            theme: Theme::default(),
+            overflow: Overflow::default(),
+            footer_mode: FooterMode::default(),
+            index_mode: IndexMode::default(),
+        }
     }
-}
 
-#[async_trait]
-impl PipelineElement for TableCommand {
-    async fn connect(&mut self, input: Option<Connector>) -> Result<(), PipelineError> {
-        self.input = input;
+    /// Selects a border theme by name (`rounded`, `heavy`, `compact`,
+    /// `markdown`, `none`, `light`); unknown names fall back to `normal`.
+    pub fn with_theme(mut self, theme: &str) -> TableCommand {
+        self.theme = Theme::from_name(theme);
+        self
+    }
 
-        Ok(())
+    /// Selects how over-wide cells give up their extra width (`wrap`,
+    /// `truncate`, `truncate-keep-words`); unknown names fall back to `wrap`.
+    pub fn with_overflow(mut self, overflow: &str) -> TableCommand {
+        self.overflow = Overflow::from_name(overflow);
+        self
+    }
+
+    /// Selects whether the header row repeats at the bottom of the table
+    /// (`auto`, `always`, `never`); unknown names fall back to `auto`.
+    pub fn with_footer(mut self, footer_mode: &str) -> TableCommand {
+        self.footer_mode = FooterMode::from_name(footer_mode);
+        self
     }
-    async fn next(&mut self) -> Result<Option<ReturnValue>, PipelineError> {
-        self.table().await
-        // if let Some(input) = &mut self.input {
-        //     if let Some(res) = input.next().await? {
-        //         // return Ok(Some(ReturnSuccess::Value(res)));
-        //     }
-        // }
 
-        // Ok(None)
+    /// Selects whether the synthesized index column is shown (`auto`,
+    /// `always`, `never`); unknown names fall back to `auto`.
+    pub fn with_index(mut self, index_mode: &str) -> TableCommand {
+        self.index_mode = IndexMode::from_name(index_mode);
+        self
+    }
+}
+
+impl PipelineElement for TableCommand {
+    fn run(self: Box<Self>, input: OutStream<Value>) -> OutStream<ReturnValue> {
+        // `table` is a sink: it prints pages as a side effect and has no
+        // values of its own to hand downstream, so its output stream is
+        // always empty.
+        Box::new(
+            stream::once(TableCommand::table(
+                input,
+                self.theme,
+                self.overflow,
+                self.footer_mode,
+                self.index_mode,
+            ))
+            .flat_map(|_| stream::empty()),
+        )
     }
 }
 
@@ -457,100 +783,82 @@ const STREAM_PAGE_SIZE: usize = 1000;
 const STREAM_TIMEOUT_CHECK_INTERVAL: usize = 100;
 
 impl TableCommand {
-    async fn table(&mut self) -> Result<Option<ReturnValue>, PipelineError> {
-        // let registry = registry.clone();
-        // let stream = async_stream! {
-        //     let mut args = args.evaluate_once(&registry).await?;
+    async fn table(
+        mut input: OutStream<Value>,
+        theme: Theme,
+        overflow: Overflow,
+        footer_mode: FooterMode,
+        index_mode: IndexMode,
+    ) {
         let mut finished = false;
-
-        //     let host = args.host.clone();
-        // let mut start_number = match args.get("start_number") {
-        //     Some(Value { value: UntaggedValue::Primitive(Primitive::Int(i)), .. }) => {
-        //         if let Some(num) = i.to_usize() {
-        //             num
-        //         } else {
-        //             yield Err(ShellError::labeled_error("Expected a row number", "expected a row number", &args.args.call_info.name_tag));
-        //             0
-        //         }
-        //     }
-        //     _ => {
-        //         0
-        //     }
-        // };
         let mut start_number = 0;
 
         let mut delay_slot = None;
 
-        if let Some(input) = &mut self.input {
-            while !finished {
-                let mut new_input: Vec<Value> = vec![];
+        while !finished {
+            let mut new_input: Vec<Value> = vec![];
 
-                let start_time = Instant::now();
-                for idx in 0..STREAM_PAGE_SIZE {
-                    if let Some(val) = delay_slot {
-                        new_input.push(val);
-                        delay_slot = None;
-                    } else {
-                        match input.next().await? {
-                            Some(a) => {
-                                if !new_input.is_empty() {
-                                    if let Some(descs) = new_input.get(0) {
-                                        let descs = descs.column_names();
-                                        let compare = a.column_names();
-                                        if descs != compare {
-                                            delay_slot = Some(a);
-                                            break;
-                                        } else {
-                                            new_input.push(a);
-                                        }
+            let start_time = Instant::now();
+            for idx in 0..STREAM_PAGE_SIZE {
+                if let Some(val) = delay_slot {
+                    new_input.push(val);
+                    delay_slot = None;
+                } else {
+                    match input.next().await {
+                        Some(a) => {
+                            if !new_input.is_empty() {
+                                if let Some(descs) = new_input.get(0) {
+                                    let descs = descs.column_names();
+                                    let compare = a.column_names();
+                                    if descs != compare {
+                                        delay_slot = Some(a);
+                                        break;
                                     } else {
                                         new_input.push(a);
                                     }
                                 } else {
                                     new_input.push(a);
                                 }
+                            } else {
+                                new_input.push(a);
                             }
-                            _ => {
-                                finished = true;
-                                break;
-                            }
                         }
+                        _ => {
+                            finished = true;
+                            break;
+                        }
+                    }
 
-                        // Check if we've gone over our buffering threshold
-                        if (idx + 1) % STREAM_TIMEOUT_CHECK_INTERVAL == 0 {
-                            let end_time = Instant::now();
+                    // Check if we've gone over our buffering threshold
+                    if (idx + 1) % STREAM_TIMEOUT_CHECK_INTERVAL == 0 {
+                        let end_time = Instant::now();
 
-                            // If we've been buffering over a second, go ahead and send out what we have so far
-                            if (end_time - start_time).as_secs() >= 1 {
-                                break;
-                            }
+                        // If we've been buffering over a second, go ahead and send out what we have so far
+                        if (end_time - start_time).as_secs() >= 1 {
+                            break;
                         }
                     }
                 }
+            }
 
-                let input: Vec<Value> = new_input.into();
+            let input: Vec<Value> = new_input.into();
 
-                if input.len() > 0 {
-                    // let mut host = host.lock();
-                    let view = TableView::from_list(&input, start_number);
+            if input.len() > 0 {
+                let view = TableView::from_list(
+                    &input,
+                    start_number,
+                    theme,
+                    overflow,
+                    footer_mode,
+                    index_mode,
+                );
 
-                    if let Some(view) = view {
-                        //handle_unexpected(&mut *host, |host| crate::format::print_view(&view, host));
-                        let _ = view.print_view();
-                    }
+                if let Some(view) = view {
+                    let _ = view.print_view();
                 }
-
-                start_number += input.len();
             }
-        }
 
-        Ok(None)
-        // Needed for async_stream to type check
-        // if false {
-        //     yield ReturnSuccess::value(UntaggedValue::nothing().into_value(Tag::unknown()));
-        // }
-        // };
-
-        // Ok(OutputStream::new(stream))
+            start_number += input.len();
+        }
     }
 }
@@ -0,0 +1,112 @@
+use prettytable::format::{FormatBuilder, LinePosition, LineSeparator};
+
+type Corners = (char, char, char, char);
+
+/// Border glyphs for a table, swappable via `TableCommand::with_theme`. Each preset
+/// supplies the column separator plus the horizontal/junction/corner
+/// glyphs for the top, title (header/body divider), and bottom rules.
+#[derive(Clone, Copy)]
+pub struct Theme {
+    column_separator: char,
+    top: Corners,
+    title: Corners,
+    bottom: Corners,
+}
+
+impl Theme {
+    pub fn from_name(name: &str) -> Theme {
+        match name {
+            "light" => Theme::light(),
+            "rounded" => Theme::rounded(),
+            "heavy" => Theme::heavy(),
+            "compact" => Theme::compact(),
+            "markdown" => Theme::markdown(),
+            "none" => Theme::none(),
+            _ => Theme::normal(),
+        }
+    }
+
+    pub fn normal() -> Theme {
+        Theme {
+            column_separator: '│',
+            top: ('─', '┬', ' ', ' '),
+            title: ('─', '┼', ' ', ' '),
+            bottom: ('─', '┴', ' ', ' '),
+        }
+    }
+
+    pub fn light() -> Theme {
+        Theme {
+            column_separator: ' ',
+            top: (' ', ' ', ' ', ' '),
+            title: ('─', '─', ' ', ' '),
+            bottom: (' ', ' ', ' ', ' '),
+        }
+    }
+
+    pub fn rounded() -> Theme {
+        Theme {
+            column_separator: '│',
+            top: ('─', '┬', '╭', '╮'),
+            title: ('─', '┼', '├', '┤'),
+            bottom: ('─', '┴', '╰', '╯'),
+        }
+    }
+
+    pub fn heavy() -> Theme {
+        Theme {
+            column_separator: '┃',
+            top: ('━', '┳', '┏', '┓'),
+            title: ('━', '╋', '┣', '┫'),
+            bottom: ('━', '┻', '┗', '┛'),
+        }
+    }
+
+    pub fn compact() -> Theme {
+        Theme {
+            column_separator: '│',
+            top: (' ', ' ', ' ', ' '),
+            title: (' ', ' ', ' ', ' '),
+            bottom: (' ', ' ', ' ', ' '),
+        }
+    }
+
+    /// `|`-delimited with a `---` title rule, so output pastes straight
+    /// into Markdown.
+    pub fn markdown() -> Theme {
+        Theme {
+            column_separator: '|',
+            top: (' ', ' ', ' ', ' '),
+            title: ('-', '|', '|', '|'),
+            bottom: (' ', ' ', ' ', ' '),
+        }
+    }
+
+    pub fn none() -> Theme {
+        Theme {
+            column_separator: ' ',
+            top: (' ', ' ', ' ', ' '),
+            title: (' ', ' ', ' ', ' '),
+            bottom: (' ', ' ', ' ', ' '),
+        }
+    }
+
+    pub fn build_format(&self) -> FormatBuilder {
+        FormatBuilder::new()
+            .column_separator(self.column_separator)
+            .separator(LinePosition::Top, line_separator(self.top))
+            .separator(LinePosition::Title, line_separator(self.title))
+            .separator(LinePosition::Bottom, line_separator(self.bottom))
+            .padding(1, 1)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme::normal()
+    }
+}
+
+fn line_separator(corners: Corners) -> LineSeparator {
+    LineSeparator::new(corners.0, corners.1, corners.2, corners.3)
+}